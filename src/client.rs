@@ -1,23 +1,119 @@
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::TransactionalBody;
+
 const BASE_URL: &str = "https://api.sendinblue.com/v3";
 
+/// Where `send_transactional_email` (and future send methods) actually
+/// deliver messages.
+#[derive(Debug, Clone, Default)]
+pub enum Transport {
+  /// Call the real Sendinblue API.
+  #[default]
+  Api,
+  /// Write each send to its own JSON file inside the `path` directory,
+  /// named by an incrementing counter, instead of making a network call.
+  File(PathBuf, Arc<AtomicU64>),
+  /// Push each send onto an in-memory buffer instead of making a network call.
+  Capture(Arc<Mutex<Vec<TransactionalBody>>>),
+}
+
 #[derive(Debug, Clone)]
 pub struct Client {
   pub server_url: String,
   pub api_key: String,
+  pub(crate) transport: Transport,
+  pub(crate) http: reqwest::Client,
 }
 
 impl Client {
+  pub fn new(server_url: String, api_key: String) -> Self {
+    ClientBuilder::new(server_url, api_key).build()
+  }
+
+  pub fn production(api_key: String) -> Self {
+    ClientBuilder::new(BASE_URL.to_string(), api_key).build()
+  }
+
+  /// Build a client that writes every send to its own JSON file inside the
+  /// `path` directory instead of calling the live API, for local testing
+  /// without a key.
+  pub fn file_transport(path: PathBuf) -> Self {
+    let mut client = ClientBuilder::new(BASE_URL.to_string(), String::new()).build();
+    client.transport = Transport::File(path, Arc::new(AtomicU64::new(0)));
+    client
+  }
+
+  /// Build a client that captures every send into an in-memory buffer
+  /// instead of calling the live API, returning the client alongside the
+  /// buffer so callers can inspect what was sent.
+  pub fn capture_transport() -> (Self, Arc<Mutex<Vec<TransactionalBody>>>) {
+    let buffer = Arc::new(Mutex::new(Vec::new()));
+    let mut client = ClientBuilder::new(BASE_URL.to_string(), String::new()).build();
+    client.transport = Transport::Capture(buffer.clone());
+    (client, buffer)
+  }
+
+  /// Starting point for configuring a client with a custom timeout, base
+  /// URL, or a shared `reqwest::Client` instead of the defaults `new` and
+  /// `production` use.
+  pub fn builder(api_key: String) -> ClientBuilder {
+    ClientBuilder::new(BASE_URL.to_string(), api_key)
+  }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ClientBuilder {
+  server_url: String,
+  api_key: String,
+  timeout: Option<Duration>,
+  http: Option<reqwest::Client>,
+}
+
+impl ClientBuilder {
   pub fn new(server_url: String, api_key: String) -> Self {
     Self {
       server_url,
       api_key,
+      timeout: None,
+      http: None,
     }
   }
 
-  pub fn production(api_key: String) -> Self {
-    Self {
-      server_url: BASE_URL.to_string(),
-      api_key,
+  pub fn server_url(mut self, server_url: String) -> Self {
+    self.server_url = server_url;
+    self
+  }
+
+  pub fn timeout(mut self, timeout: Duration) -> Self {
+    self.timeout = Some(timeout);
+    self
+  }
+
+  /// Use an already-built `reqwest::Client` (e.g. one shared with other
+  /// services) instead of letting the builder construct its own.
+  pub fn http_client(mut self, http: reqwest::Client) -> Self {
+    self.http = Some(http);
+    self
+  }
+
+  pub fn build(self) -> Client {
+    let http = self.http.unwrap_or_else(|| {
+      let mut builder = reqwest::Client::builder();
+      if let Some(timeout) = self.timeout {
+        builder = builder.timeout(timeout);
+      }
+      builder.build().expect("failed to build reqwest client")
+    });
+
+    Client {
+      server_url: self.server_url,
+      api_key: self.api_key,
+      transport: Transport::Api,
+      http,
     }
   }
 }
@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{error::{parse_empty_response, parse_response}, Sendinblue, SendinblueError};
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Contact {
+  pub(crate) email: String,
+  #[serde(skip_serializing_if = "Value::is_null")]
+  pub(crate) attributes: Value,
+  #[serde(rename = "listIds", skip_serializing_if = "Vec::is_empty")]
+  pub(crate) list_ids: Vec<u32>,
+  #[serde(rename = "updateEnabled")]
+  pub(crate) update_enabled: bool,
+}
+
+impl Contact {
+  pub fn builder() -> ContactBuilder {
+    ContactBuilder(Contact::default())
+  }
+}
+
+pub struct ContactBuilder(Contact);
+
+impl ContactBuilder {
+  pub fn email<S>(self, email: S) -> Self
+  where
+    S: Into<String>,
+  {
+    let mut inner = self.0;
+    inner.email = email.into();
+    Self(inner)
+  }
+
+  pub fn add_attribute<S>(self, key: &str, value: S) -> Self
+  where
+    S: Into<String>,
+  {
+    let mut inner = self.0;
+    inner.attributes[key] = Value::String(value.into());
+    Self(inner)
+  }
+
+  pub fn add_list_id(self, list_id: u32) -> Self {
+    let mut inner = self.0;
+    inner.list_ids.push(list_id);
+    Self(inner)
+  }
+
+  pub fn update_enabled(self, update_enabled: bool) -> Self {
+    let mut inner = self.0;
+    inner.update_enabled = update_enabled;
+    Self(inner)
+  }
+
+  pub fn create(self) -> Contact {
+    self.0
+  }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContactList {
+  pub id: u32,
+  pub name: String,
+  #[serde(rename = "folderId")]
+  pub folder_id: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListContactsResp {
+  lists: Vec<ContactList>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateListBody<'a> {
+  name: &'a str,
+  #[serde(rename = "folderId")]
+  folder_id: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct UpdateListContactsBody<'a> {
+  emails: &'a [String],
+}
+
+impl Sendinblue {
+  /// Creates or, if `update_enabled` is set and the email already exists,
+  /// updates a contact (`POST /contacts`).
+  pub async fn create_contact(&self, contact: &Contact) -> Result<(), SendinblueError> {
+    let url = format!("{}/contacts", self.server_url);
+
+    let response = self
+      .http
+      .post(&url)
+      .header("api-key", &self.api_key)
+      .header("accept", "application/json")
+      .header("content-type", "application/json")
+      .json(contact)
+      .send()
+      .await?;
+
+    parse_empty_response(response).await
+  }
+
+  /// Adds `emails` to the list identified by `list_id`
+  /// (`POST /contacts/lists/{listId}/contacts/add`).
+  pub async fn add_contacts_to_list(&self, list_id: u32, emails: &[String]) -> Result<(), SendinblueError> {
+    let url = format!("{}/contacts/lists/{}/contacts/add", self.server_url, list_id);
+    let body = UpdateListContactsBody { emails };
+
+    let response = self
+      .http
+      .post(&url)
+      .header("api-key", &self.api_key)
+      .header("accept", "application/json")
+      .header("content-type", "application/json")
+      .json(&body)
+      .send()
+      .await?;
+
+    parse_empty_response(response).await
+  }
+
+  /// Removes `emails` from the list identified by `list_id`
+  /// (`POST /contacts/lists/{listId}/contacts/remove`).
+  pub async fn remove_contacts_from_list(&self, list_id: u32, emails: &[String]) -> Result<(), SendinblueError> {
+    let url = format!("{}/contacts/lists/{}/contacts/remove", self.server_url, list_id);
+    let body = UpdateListContactsBody { emails };
+
+    let response = self
+      .http
+      .post(&url)
+      .header("api-key", &self.api_key)
+      .header("accept", "application/json")
+      .header("content-type", "application/json")
+      .json(&body)
+      .send()
+      .await?;
+
+    parse_empty_response(response).await
+  }
+
+  /// Creates a new list in `folder_id` (`POST /contacts/lists`).
+  pub async fn create_list(&self, name: &str, folder_id: u32) -> Result<ContactList, SendinblueError> {
+    let url = format!("{}/contacts/lists", self.server_url);
+    let body = CreateListBody { name, folder_id };
+
+    let response = self
+      .http
+      .post(&url)
+      .header("api-key", &self.api_key)
+      .header("accept", "application/json")
+      .header("content-type", "application/json")
+      .json(&body)
+      .send()
+      .await?;
+
+    parse_response(response).await
+  }
+
+  /// Fetches every list (`GET /contacts/lists`).
+  pub async fn list_contact_lists(&self) -> Result<Vec<ContactList>, SendinblueError> {
+    let url = format!("{}/contacts/lists", self.server_url);
+
+    let response = self
+      .http
+      .get(&url)
+      .header("api-key", &self.api_key)
+      .header("accept", "application/json")
+      .send()
+      .await?;
+
+    let resp: ListContactsResp = parse_response(response).await?;
+    Ok(resp.lists)
+  }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+/// Error returned by every Sendinblue API call.
+#[derive(Debug)]
+pub enum SendinblueError {
+  /// The request never made it to (or back from) Sendinblue.
+  Transport(reqwest::Error),
+  /// Sendinblue responded with a non-2xx status and a structured error body.
+  Api {
+    status: u16,
+    code: String,
+    message: String,
+  },
+  /// The response body could not be parsed as the expected shape, or a
+  /// request body could not be serialized to JSON.
+  Decode(serde_json::Error),
+  /// A file-backed transport failed to read or write its target path.
+  Io(std::io::Error),
+}
+
+impl fmt::Display for SendinblueError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      SendinblueError::Transport(err) => write!(f, "sendinblue transport error: {}", err),
+      SendinblueError::Api {
+        status,
+        code,
+        message,
+      } => write!(f, "sendinblue api error {} ({}): {}", status, code, message),
+      SendinblueError::Decode(err) => write!(f, "sendinblue JSON encode/decode error: {}", err),
+      SendinblueError::Io(err) => write!(f, "sendinblue file transport error: {}", err),
+    }
+  }
+}
+
+impl std::error::Error for SendinblueError {}
+
+impl From<reqwest::Error> for SendinblueError {
+  fn from(err: reqwest::Error) -> Self {
+    SendinblueError::Transport(err)
+  }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApiErrorBody {
+  pub(crate) code: String,
+  pub(crate) message: String,
+}
+
+/// Turns a `reqwest::Response` into either the deserialized success body or a
+/// [`SendinblueError`], shared by every endpoint that returns a JSON body so
+/// error handling stays consistent across the crate.
+pub(crate) async fn parse_response<T>(response: reqwest::Response) -> Result<T, SendinblueError>
+where
+  T: serde::de::DeserializeOwned,
+{
+  let status = response.status();
+  let bytes = response.bytes().await?;
+
+  if status.is_success() {
+    serde_json::from_slice(&bytes).map_err(SendinblueError::Decode)
+  } else {
+    let error_body: ApiErrorBody = serde_json::from_slice(&bytes).map_err(SendinblueError::Decode)?;
+    Err(SendinblueError::Api {
+      status: status.as_u16(),
+      code: error_body.code,
+      message: error_body.message,
+    })
+  }
+}
+
+/// Like [`parse_response`] but for endpoints that reply with an empty body
+/// (e.g. `204 No Content`) on success.
+pub(crate) async fn parse_empty_response(response: reqwest::Response) -> Result<(), SendinblueError> {
+  let status = response.status();
+
+  if status.is_success() {
+    Ok(())
+  } else {
+    let bytes = response.bytes().await?;
+    let error_body: ApiErrorBody = serde_json::from_slice(&bytes).map_err(SendinblueError::Decode)?;
+    Err(SendinblueError::Api {
+      status: status.as_u16(),
+      code: error_body.code,
+      message: error_body.message,
+    })
+  }
+}
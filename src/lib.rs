@@ -2,9 +2,14 @@
 extern crate log;
 
 mod client;
+mod contacts;
+mod error;
 mod mailer;
 mod transactional;
 
+pub use client::Transport;
+pub use contacts::*;
+pub use error::SendinblueError;
 pub use mailer::Mailer;
 pub use transactional::*;
 
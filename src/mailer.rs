@@ -16,4 +16,8 @@ impl Mailer {
       email: email.into(),
     }
   }
+
+  pub(crate) fn is_empty(&self) -> bool {
+    self.name.is_empty() && self.email.is_empty()
+  }
 }
@@ -1,17 +1,41 @@
+use std::collections::HashMap;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
-use crate::{mailer::Mailer, Sendinblue};
+use crate::{client::Transport, error::parse_response, mailer::Mailer, Sendinblue, SendinblueError};
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionalBody {
+  #[serde(skip_serializing_if = "Mailer::is_empty")]
   pub(crate) sender: Mailer,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
   pub(crate) to: Vec<Mailer>,
+  #[serde(skip_serializing_if = "Mailer::is_empty")]
   pub(crate) reply_to: Mailer,
-  pub(crate) template_id: u32,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) template_id: Option<u32>,
   pub(crate) subject: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) html_content: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) text_content: Option<String>,
   pub(crate) params: Value,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) attachment: Vec<Attachment>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) message_versions: Vec<MessageVersion>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) scheduled_at: Option<String>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub(crate) tags: Vec<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) batch_id: Option<String>,
+  #[serde(skip_serializing_if = "HashMap::is_empty")]
+  pub(crate) headers: HashMap<String, String>,
 }
 
 impl TransactionalBody {
@@ -44,7 +68,9 @@ impl TransactionalBodyBuilder {
 
   pub fn template_id(self, template_id: u32) -> Self {
     let mut inner = self.0;
-    inner.template_id = template_id;
+    inner.template_id = Some(template_id);
+    inner.html_content = None;
+    inner.text_content = None;
     Self(inner)
   }
 
@@ -57,6 +83,26 @@ impl TransactionalBodyBuilder {
     Self(inner)
   }
 
+  pub fn html_content<S>(self, html_content: S) -> Self
+  where
+    S: Into<String>,
+  {
+    let mut inner = self.0;
+    inner.html_content = Some(html_content.into());
+    inner.template_id = None;
+    Self(inner)
+  }
+
+  pub fn text_content<S>(self, text_content: S) -> Self
+  where
+    S: Into<String>,
+  {
+    let mut inner = self.0;
+    inner.text_content = Some(text_content.into());
+    inner.template_id = None;
+    Self(inner)
+  }
+
   pub fn add_params<S>(self, key: &str, value: S) -> Self
   where
     S: Into<String>,
@@ -75,6 +121,89 @@ impl TransactionalBodyBuilder {
     Self(inner)
   }
 
+  pub fn add_attachment_url<S>(self, name: S, url: S) -> Self
+  where
+    S: Into<String>,
+  {
+    let mut inner = self.0;
+    inner.attachment.push(Attachment::Url {
+      url: url.into(),
+      name: name.into(),
+    });
+    Self(inner)
+  }
+
+  pub fn add_attachment_bytes<S>(self, name: S, bytes: &[u8]) -> Self
+  where
+    S: Into<String>,
+  {
+    let mut inner = self.0;
+    inner.attachment.push(Attachment::Content {
+      content: STANDARD.encode(bytes),
+      name: name.into(),
+    });
+    Self(inner)
+  }
+
+  pub fn add_message_version(self, version: MessageVersion) -> Self {
+    let mut inner = self.0;
+    inner.message_versions.push(version);
+    Self(inner)
+  }
+
+  /// Splits `recipients` into chunks of at most `batch_size` and adds one
+  /// [`MessageVersion`] per chunk, all sharing `params`. A `batch_size` of 0
+  /// is treated as 1 (one recipient per version). Useful for turning a
+  /// large mailout into a handful of `messageVersions` instead of one HTTP
+  /// call per recipient.
+  pub fn add_recipient_batches(self, recipients: Vec<Mailer>, params: Value, batch_size: usize) -> Self {
+    let mut inner = self.0;
+    for chunk in recipients.chunks(batch_size.max(1)) {
+      inner.message_versions.push(MessageVersion::new(chunk.to_vec(), params.clone()));
+    }
+    Self(inner)
+  }
+
+  /// Sets the send-later time. `scheduled_at` must have a year in
+  /// `0000..=9999`, the only range RFC3339 can represent; outside of that,
+  /// `OffsetDateTime::format` fails and this leaves the body unchanged (the
+  /// send then goes out immediately instead of being scheduled).
+  pub fn scheduled_at(self, scheduled_at: OffsetDateTime) -> Self {
+    let mut inner = self.0;
+    match scheduled_at.format(&Rfc3339) {
+      Ok(formatted) => inner.scheduled_at = Some(formatted),
+      Err(err) => warn!("scheduled_at: failed to format {:?} as RFC3339: {}", scheduled_at, err),
+    }
+    Self(inner)
+  }
+
+  pub fn add_tag<S>(self, tag: S) -> Self
+  where
+    S: Into<String>,
+  {
+    let mut inner = self.0;
+    inner.tags.push(tag.into());
+    Self(inner)
+  }
+
+  pub fn batch_id<S>(self, batch_id: S) -> Self
+  where
+    S: Into<String>,
+  {
+    let mut inner = self.0;
+    inner.batch_id = Some(batch_id.into());
+    Self(inner)
+  }
+
+  pub fn add_header<S>(self, key: S, value: S) -> Self
+  where
+    S: Into<String>,
+  {
+    let mut inner = self.0;
+    inner.headers.insert(key.into(), value.into());
+    Self(inner)
+  }
+
   pub fn create(self) -> TransactionalBody {
     self.0
   }
@@ -110,6 +239,44 @@ impl TransactionalBodyBuilder {
   }
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum Attachment {
+  Url { url: String, name: String },
+  Content { content: String, name: String },
+}
+
+/// Per-recipient substitution data and overrides for a single
+/// `TransactionalBody`, letting one request carry different `params` (and
+/// optionally a different subject) for each recipient or batch of
+/// recipients.
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageVersion {
+  pub(crate) to: Vec<Mailer>,
+  pub(crate) params: Value,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) subject: Option<String>,
+}
+
+impl MessageVersion {
+  pub fn new(to: Vec<Mailer>, params: Value) -> Self {
+    Self {
+      to,
+      params,
+      subject: None,
+    }
+  }
+
+  pub fn subject<S>(mut self, subject: S) -> Self
+  where
+    S: Into<String>,
+  {
+    self.subject = Some(subject.into());
+    self
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionalResp {
@@ -120,21 +287,45 @@ impl Sendinblue {
   pub async fn send_transactional_email(
     &self,
     body: TransactionalBody,
-  ) -> Result<TransactionalResp, reqwest::Error> {
+  ) -> Result<TransactionalResp, SendinblueError> {
     debug!(
       "send_transactional_email: {}",
       serde_json::to_string_pretty(&body).unwrap()
     );
 
+    match &self.transport {
+      Transport::File(dir, counter) => {
+        std::fs::create_dir_all(dir).map_err(SendinblueError::Io)?;
+
+        let seq = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        let message_id = format!("file-transport-{}", seq);
+        let json = serde_json::to_string_pretty(&body).map_err(SendinblueError::Decode)?;
+        std::fs::write(dir.join(format!("message-{:06}.json", seq)), json).map_err(SendinblueError::Io)?;
+
+        return Ok(TransactionalResp { message_id });
+      }
+      Transport::Capture(buffer) => {
+        buffer.lock().unwrap().push(body);
+        return Ok(TransactionalResp {
+          message_id: "capture-transport".to_string(),
+        });
+      }
+      Transport::Api => {}
+    }
+
     let url = format!("{}/smtp/email", self.server_url);
 
-    reqwest::Client::new()
+    let response = self
+      .http
       .post(&url)
+      .header("api-key", &self.api_key)
+      .header("accept", "application/json")
+      .header("content-type", "application/json")
       .json(&body)
       .send()
-      .await?
-      .json()
-      .await
+      .await?;
+
+    parse_response(response).await
   }
 }
 
@@ -142,8 +333,10 @@ impl Sendinblue {
 mod test {
   use crate::*;
 
+  use base64::{engine::general_purpose::STANDARD, Engine};
   use dotenv::dotenv;
   use serde::Serialize;
+  use time::{Date, Month, OffsetDateTime, Time};
 
   #[derive(Debug, Serialize)]
   struct Required {
@@ -323,4 +516,105 @@ mod test {
 
     debug!("payload: {:?}", payload);
   }
+
+  #[test]
+  fn test_template_id_and_content_are_mutually_exclusive() {
+    let with_content_then_template = TransactionalBody::builder()
+      .html_content("<p>hi</p>")
+      .template_id(36)
+      .create();
+
+    assert_eq!(with_content_then_template.template_id, Some(36));
+    assert_eq!(with_content_then_template.html_content, None);
+
+    let with_template_then_content = TransactionalBody::builder()
+      .template_id(36)
+      .html_content("<p>hi</p>")
+      .text_content("hi")
+      .create();
+
+    assert_eq!(with_template_then_content.template_id, None);
+    assert_eq!(with_template_then_content.html_content, Some("<p>hi</p>".to_string()));
+    assert_eq!(with_template_then_content.text_content, Some("hi".to_string()));
+  }
+
+  #[test]
+  fn test_add_recipient_batches_chunks_recipients() {
+    let recipients = vec![
+      Mailer::new("a", "a@example.com"),
+      Mailer::new("b", "b@example.com"),
+      Mailer::new("c", "c@example.com"),
+      Mailer::new("d", "d@example.com"),
+      Mailer::new("e", "e@example.com"),
+    ];
+
+    let payload = TransactionalBody::builder()
+      .add_recipient_batches(recipients.clone(), serde_json::json!({"k": "v"}), 2)
+      .create();
+
+    assert_eq!(payload.message_versions.len(), 3);
+    assert_eq!(payload.message_versions[0].to.len(), 2);
+    assert_eq!(payload.message_versions[1].to.len(), 2);
+    assert_eq!(payload.message_versions[2].to.len(), 1);
+
+    // a batch_size of 0 must not panic, and should fall back to one recipient per version
+    let payload = TransactionalBody::builder()
+      .add_recipient_batches(recipients, serde_json::json!({"k": "v"}), 0)
+      .create();
+
+    assert_eq!(payload.message_versions.len(), 5);
+  }
+
+  #[test]
+  fn test_attachment_shapes() {
+    let payload = TransactionalBody::builder()
+      .add_attachment_url("invoice.pdf", "https://example.com/invoice.pdf")
+      .add_attachment_bytes("receipt.txt", b"hello")
+      .create();
+
+    let value = serde_json::to_value(&payload).unwrap();
+    let attachments = value["attachment"].as_array().unwrap();
+
+    assert_eq!(
+      attachments[0],
+      serde_json::json!({"url": "https://example.com/invoice.pdf", "name": "invoice.pdf"})
+    );
+    assert_eq!(
+      attachments[1],
+      serde_json::json!({"content": STANDARD.encode(b"hello"), "name": "receipt.txt"})
+    );
+  }
+
+  #[test]
+  fn test_scheduled_at_out_of_rfc3339_range_leaves_body_unchanged() {
+    let unrepresentable = OffsetDateTime::new_utc(
+      Date::from_calendar_date(-1, Month::January, 1).unwrap(),
+      Time::MIDNIGHT,
+    );
+
+    let payload = TransactionalBody::builder().scheduled_at(unrepresentable).create();
+
+    assert_eq!(payload.scheduled_at, None);
+  }
+
+  #[tokio::test]
+  async fn test_capture_transport_collects_sends_without_a_network_call() {
+    let (client, buffer) = Sendinblue::capture_transport();
+
+    let body = TransactionalBody::builder()
+      .set_sender(Mailer::new("sender", "sender@example.com"))
+      .add_to_mailer(Mailer::new("receiver", "receiver@example.com"))
+      .subject("hello")
+      .html_content("<p>hi</p>")
+      .create();
+
+    let resp = client.send_transactional_email(body.clone()).await.unwrap();
+
+    assert_eq!(resp.message_id, "capture-transport");
+
+    let captured = buffer.lock().unwrap();
+    assert_eq!(captured.len(), 1);
+    assert_eq!(captured[0].subject, body.subject);
+    assert_eq!(captured[0].html_content, body.html_content);
+  }
 }